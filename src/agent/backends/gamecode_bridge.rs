@@ -4,44 +4,76 @@ use gamecode_bedrock::BedrockBackend;
 use gamecode_tools::jsonrpc::Dispatcher;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{error, trace};
 use uuid::Uuid;
 
-use super::{Backend, BackendCore, BackendResponse, ToolUse};
+use super::{Backend, BackendCore, BackendResponse, ModelConfig, ResponseDelta, ToolUse};
 
 /// Bridge that adapts our modular gamecode architecture to the existing desktop UI interface
 pub struct GamecodeBridge {
-    /// The modular gamecode backend
-    backend: BedrockBackend,
-    
+    /// The modular gamecode backend for the active provider
+    backend: Box<dyn LLMBackend>,
+
+    /// The model this bridge is configured to drive
+    active_model: ModelConfig,
+
     /// Gamecode tools dispatcher
     tool_dispatcher: Dispatcher,
-    
+
     /// Tool schema registry (same instance as dispatcher)
     tool_schema_registry: gamecode_tools::schema::ToolSchemaRegistry,
-    
+
     /// Current session ID
     session_id: Uuid,
-    
+
     /// Retry configuration
     retry_config: RetryConfig,
 }
 
 impl GamecodeBridge {
-    pub async fn new(region: &str, profile: Option<String>) -> Result<Self, BackendError> {
-        let backend = BedrockBackend::new().await.map_err(|e| BackendError::NetworkError { message: e.to_string() })?;
+    pub async fn new(
+        models: &[ModelConfig],
+        region: &str,
+        profile: Option<String>,
+    ) -> Result<Self, BackendError> {
+        // The first declared model is the active one; fall back to the bedrock default
+        // so callers that don't configure any models keep working.
+        let active_model = models.first().cloned().unwrap_or_default();
+
+        let backend = Self::build_backend(&active_model, region, profile).await?;
         let (tool_dispatcher, tool_schema_registry) = gamecode_tools::create_bedrock_dispatcher_with_schemas();
         let session_id = Uuid::new_v4();
         let retry_config = RetryConfig::default();
-        
+
         Ok(Self {
             backend,
+            active_model,
             tool_dispatcher,
             tool_schema_registry,
             session_id,
             retry_config,
         })
     }
+
+    /// Dispatch to the `LLMBackend` implementation matching the model's provider.
+    async fn build_backend(
+        model: &ModelConfig,
+        _region: &str,
+        _profile: Option<String>,
+    ) -> Result<Box<dyn LLMBackend>, BackendError> {
+        match model.provider.as_str() {
+            "bedrock" => {
+                let backend = BedrockBackend::new()
+                    .await
+                    .map_err(|e| BackendError::NetworkError { message: e.to_string() })?;
+                Ok(Box::new(backend))
+            }
+            other => Err(BackendError::NetworkError {
+                message: format!("Unsupported backend provider: '{}'", other),
+            }),
+        }
+    }
     
     /// Convert desktop UI message format to backend message format
     fn convert_to_backend_message(role: &str, content: &str) -> BackendMessage {
@@ -73,6 +105,105 @@ impl GamecodeBridge {
         }).collect()
     }
     
+    /// Build a `ChatRequest` for the given prompt, optionally wiring a status
+    /// callback so the backend can forward incremental updates while streaming.
+    fn build_request(
+        &self,
+        prompt: &str,
+        status_callback: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    ) -> ChatRequest {
+        // Parse the context to extract messages - assume it's formatted properly
+        let messages = vec![Self::convert_to_backend_message("user", prompt)];
+
+        // Get available tools from our stored schema registry
+        let tool_specs = self.tool_schema_registry.to_bedrock_specs();
+
+        let tools: Vec<BackendTool> = tool_specs
+            .into_iter()
+            .map(|spec| BackendTool {
+                name: spec.name,
+                description: spec.description,
+                input_schema: spec.input_schema.json, // Extract the JSON Value from BedrockInputSchema
+            })
+            .collect();
+
+        let mut request = ChatRequest {
+            messages,
+            model: Some(self.active_model.model.clone()),
+            tools: Some(tools),
+            inference_config: None, // Use backend defaults unless `extra` overrides below
+            session_id: Some(self.session_id),
+            status_callback,
+        };
+
+        // Apply the model's provider-specific inference parameters by deserializing
+        // `extra` into the backend's typed `inference_config` (the only inference knob
+        // the backend request exposes). On failure we keep going with backend defaults
+        // but log loudly instead of swallowing the error with `.ok()`.
+        if !self.active_model.extra.is_null() {
+            match serde_json::from_value(self.active_model.extra.clone()) {
+                Ok(inference_config) => request.inference_config = Some(inference_config),
+                Err(e) => error!(
+                    "Ignoring `extra` for model '{}': could not apply to inference config: {}",
+                    self.active_model.model, e
+                ),
+            }
+        }
+
+        request
+    }
+
+    /// Convert a completed backend chat response into our UI-facing [`BackendResponse`].
+    fn convert_response(&self, response: gamecode_backend::ChatResponse) -> BackendResponse {
+        // Convert tool calls from backend format to UI format
+        let tool_calls = self.convert_tool_calls_to_ui(&response.tool_calls);
+
+        // Extract text content from message
+        let content = response
+            .message
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                gamecode_backend::ContentBlock::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        let backend_response = BackendResponse {
+            content,
+            model: response.model,
+            tokens_used: response.usage.map(|u| u.total_tokens as usize),
+            tool_calls,
+        };
+
+        trace!(
+            "Generated response: {} chars, {} tool calls",
+            backend_response.content.len(),
+            backend_response.tool_calls.len()
+        );
+
+        backend_response
+    }
+
+    /// Tools known to be read-only, and therefore exempt from the approval gate.
+    ///
+    /// This classification lives alongside the bridge rather than inside each tool's
+    /// argument JSON schema, so it never leaks into the parameters shipped to the model.
+    /// The list is deliberately a read-only *allowlist*: anything not named here is
+    /// treated as potentially side-effecting, so a new or unknown tool fails safe into
+    /// requiring approval rather than silently running unprompted.
+    const READ_ONLY_TOOLS: &'static [&'static str] =
+        &["read_file", "list_dir", "list_files", "grep", "search", "find"];
+
+    /// Classify whether a tool may have side effects and therefore needs approval.
+    ///
+    /// Read-only tools (see [`Self::READ_ONLY_TOOLS`]) run freely; everything else is
+    /// treated as mutating.
+    pub fn tool_may_mutate(&self, tool_name: &str) -> bool {
+        !Self::READ_ONLY_TOOLS.contains(&tool_name)
+    }
+
     /// Execute a tool using the gamecode-tools JSONRPC dispatcher
     pub async fn execute_tool(&self, tool_name: &str, arguments: &Value) -> Result<String, String> {
         trace!("Executing tool: {} with args: {}", tool_name, arguments);
@@ -106,55 +237,49 @@ impl GamecodeBridge {
 impl Backend for GamecodeBridge {
     async fn generate_response(&self, prompt: &str) -> Result<BackendResponse, String> {
         trace!("Generating response for prompt: {} chars", prompt.len());
-        
-        // Parse the context to extract messages - assume it's formatted properly
-        let messages = vec![Self::convert_to_backend_message("user", prompt)];
-        
-        // Get available tools from our stored schema registry
-        let tool_specs = self.tool_schema_registry.to_bedrock_specs();
-        
-        let tools: Vec<BackendTool> = tool_specs.into_iter()
-            .map(|spec| BackendTool {
-                name: spec.name,
-                description: spec.description,
-                input_schema: spec.input_schema.json, // Extract the JSON Value from BedrockInputSchema
-            })
-            .collect();
-        
-        let request = ChatRequest {
-            messages,
-            model: None, // Let backend choose the model
-            tools: Some(tools),
-            inference_config: None, // Use backend defaults
-            session_id: Some(self.session_id),
-            status_callback: None, // Status handled elsewhere
-        };
-        
+
+        let request = self.build_request(prompt, None); // Status handled elsewhere
+
+        match self.backend.chat_with_retry(request, self.retry_config.clone()).await {
+            Ok(response) => Ok(self.convert_response(response)),
+            Err(e) => {
+                error!("Backend error: {}", e);
+                Err(format!("Backend error: {}", e))
+            }
+        }
+    }
+
+    async fn generate_response_streaming(
+        &self,
+        prompt: &str,
+        on_delta: Box<dyn Fn(ResponseDelta) + Send + Sync>,
+    ) -> Result<BackendResponse, String> {
+        trace!("Streaming response for prompt: {} chars", prompt.len());
+
+        // Forward the backend's incremental status updates to the caller as text
+        // deltas. The callback is shared into the request, which the backend invokes
+        // as tokens arrive.
+        let on_delta = Arc::new(on_delta);
+        let text_sink = on_delta.clone();
+        let status_callback: Arc<dyn Fn(String) + Send + Sync> =
+            Arc::new(move |chunk: String| {
+                text_sink(ResponseDelta::Text(chunk));
+            });
+
+        let request = self.build_request(prompt, Some(status_callback));
+
         match self.backend.chat_with_retry(request, self.retry_config.clone()).await {
             Ok(response) => {
-                // Convert tool calls from backend format to UI format
-                let tool_calls = self.convert_tool_calls_to_ui(&response.tool_calls);
-                
-                // Extract text content from message
-                let content = response.message.content.iter()
-                    .filter_map(|block| match block {
-                        gamecode_backend::ContentBlock::Text(text) => Some(text.clone()),
-                        _ => None,
-                    })
-                    .collect::<Vec<_>>()
-                    .join("");
-                
-                let backend_response = BackendResponse {
-                    content,
-                    model: response.model,
-                    tokens_used: response.usage.map(|u| u.total_tokens as usize),
-                    tool_calls,
-                };
-                
-                trace!("Generated response: {} chars, {} tool calls", 
-                    backend_response.content.len(), 
-                    backend_response.tool_calls.len());
-                
+                let backend_response = self.convert_response(response);
+                // Emit tool-call boundaries once the buffered response is known, so the
+                // UI can close out the live token stream and render the tool timeline.
+                for tc in &backend_response.tool_calls {
+                    on_delta(ResponseDelta::ToolCallStart {
+                        name: tc.name.clone(),
+                        id: tc.id.clone(),
+                    });
+                    on_delta(ResponseDelta::ToolCallStop { id: tc.id.clone() });
+                }
                 Ok(backend_response)
             }
             Err(e) => {
@@ -171,7 +296,7 @@ impl BackendCore for GamecodeBridge {
     }
     
     fn context_window(&self) -> usize {
-        200000 // Claude 3.7 context length
+        self.active_model.max_tokens
     }
 }
 
@@ -37,6 +37,42 @@ pub fn init() {
     trace!("Initializing agent backends...");
 }
 
+/// Declarative description of a model the agent can target.
+///
+/// Each entry names a `provider` that selects which [`gamecode_backend::LLMBackend`]
+/// implementation handles it (only `"bedrock"` is handled; any other provider makes
+/// `GamecodeBridge::new` return an error), the provider-specific
+/// `model` name, and a `max_tokens` budget that drives [`BackendCore::context_window`].
+/// Rather than modelling every provider's parameters up front, `extra` carries
+/// provider-specific inference parameters applied to the request (see [`ModelConfig::extra`]).
+#[derive(Clone, Debug)]
+pub struct ModelConfig {
+    /// Provider key selecting the backend implementation
+    pub provider: String,
+
+    /// Provider-specific model name/identifier
+    pub model: String,
+
+    /// Context window / token budget for this model
+    pub max_tokens: usize,
+
+    /// Provider-specific inference parameters, supplied as JSON and deserialized into
+    /// the backend's inference config. Fields the backend's inference config does not
+    /// model are not sent — this is not an arbitrary raw-body passthrough.
+    pub extra: serde_json::Value,
+}
+
+impl Default for ModelConfig {
+    fn default() -> Self {
+        Self {
+            provider: "bedrock".to_string(),
+            model: "us.anthropic.claude-3-7-sonnet-20250219-v1:0".to_string(),
+            max_tokens: 200_000,
+            extra: serde_json::Value::Null,
+        }
+    }
+}
+
 /// Trait defining a language model backend core functionality
 pub trait BackendCore: Send + Sync {
     /// Get the backend's name
@@ -46,11 +82,51 @@ pub trait BackendCore: Send + Sync {
     fn context_window(&self) -> usize;
 }
 
+/// Incremental update emitted by a streaming backend as a response is produced.
+#[derive(Debug, Clone)]
+pub enum ResponseDelta {
+    /// A chunk of assistant text as it is generated
+    Text(String),
+
+    /// The model has started emitting a tool call
+    ToolCallStart {
+        /// Name of the tool being called
+        name: String,
+        /// Tool call ID, once known
+        id: Option<String>,
+    },
+
+    /// The model has finished emitting a tool call's arguments
+    ToolCallStop {
+        /// Tool call ID, if known
+        id: Option<String>,
+    },
+}
+
 /// Trait defining the async operations for the backend
 #[async_trait::async_trait]
 pub trait Backend: BackendCore {
     /// Generate a response from the given prompt
     async fn generate_response(&self, prompt: &str) -> Result<BackendResponse, String>;
+
+    /// Generate a response while forwarding incremental deltas to `on_delta` as they
+    /// arrive, so callers can render tokens live. The fully-assembled
+    /// [`BackendResponse`] is still returned for context bookkeeping.
+    ///
+    /// The default implementation buffers the whole response and emits it as a single
+    /// text delta, so backends without native streaming still work through this entry
+    /// point.
+    async fn generate_response_streaming(
+        &self,
+        prompt: &str,
+        on_delta: Box<dyn Fn(ResponseDelta) + Send + Sync>,
+    ) -> Result<BackendResponse, String> {
+        let response = self.generate_response(prompt).await?;
+        if !response.content.is_empty() {
+            on_delta(ResponseDelta::Text(response.content.clone()));
+        }
+        Ok(response)
+    }
 }
 
 /// Structure containing a response from an LLM backend
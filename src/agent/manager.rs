@@ -4,8 +4,60 @@ use crate::agent::tools::ToolRegistry;
 // Removed regex dependency
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::watch;
 use tracing::{error, info, trace, warn};
 
+/// The observable lifecycle state of the agent while handling a request.
+///
+/// Transitions are published on a [`watch`] channel (see
+/// [`AgentManager::subscribe_state`]) so the UI and `visualization` module can animate
+/// what the agent is doing, and recorded in a per-request history (see
+/// [`AgentManager::state_history`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AgentState {
+    /// Not currently processing a request
+    Idle,
+
+    /// Waiting on the backend to generate a response
+    Thinking,
+
+    /// Running a tool
+    ExecutingTool {
+        /// Name of the tool being executed
+        name: String,
+    },
+
+    /// Waiting on the user to approve a side-effecting tool
+    AwaitingApproval {
+        /// Name of the tool awaiting approval
+        name: String,
+    },
+
+    /// Summarizing older context to stay within the window
+    CompressingContext,
+
+    /// The last operation failed
+    Error {
+        /// Human-readable failure description
+        message: String,
+    },
+}
+
+/// Async callback invoked to approve a side-effecting tool before it runs.
+///
+/// Receives the tool name and its JSON arguments and resolves to `true` to allow
+/// execution or `false` to cancel it. The UI wires this up to an interactive prompt.
+pub type ApprovalCallback =
+    Arc<dyn Fn(String, Value) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync>;
+
+/// Callback invoked with each streaming [`ResponseDelta`] as the backend produces it,
+/// so the UI can render tokens and tool-call boundaries live.
+pub type StreamCallback =
+    Arc<dyn Fn(crate::agent::backends::ResponseDelta) + Send + Sync>;
+
 /// Central manager for the AI agent
 pub struct AgentManager {
     /// The currently active backend for LLM processing
@@ -22,6 +74,47 @@ pub struct AgentManager {
 
     /// Whether the backend is initialized
     initialized: bool,
+
+    /// Publisher for the current [`AgentState`], observed by the UI and visualization
+    state_tx: watch::Sender<AgentState>,
+
+    /// Ordered history of state transitions for the current request, for debugging
+    state_history: Vec<AgentState>,
+
+    /// Hooks fired before each tool call, in registration order
+    pre_tool_hooks: Vec<Arc<dyn PreToolHook>>,
+
+    /// Hooks fired after each tool call, in registration order
+    post_tool_hooks: Vec<Arc<dyn PostToolHook>>,
+}
+
+/// Outcome of a [`PreToolHook`], deciding what happens to a pending tool call.
+pub enum PreHookOutcome {
+    /// Proceed with the (possibly rewritten) arguments
+    Continue(Value),
+
+    /// Short-circuit the call, substituting this result instead of executing the tool.
+    /// The `tool_call_id` is stamped by the manager, so hooks may leave it `None`.
+    Replace(ToolResult),
+}
+
+/// A hook that runs before a tool executes.
+///
+/// Implementations may rewrite the arguments, inject context, or veto the call by
+/// returning [`PreHookOutcome::Replace`]. Hooks run in registration order and each sees
+/// the arguments produced by the previous one.
+pub trait PreToolHook: Send + Sync {
+    /// Inspect or rewrite a pending tool call.
+    fn before(&self, tool_name: &str, args: Value) -> PreHookOutcome;
+}
+
+/// A hook that runs after a tool executes.
+///
+/// Implementations may transform or redact the result content before it is added to
+/// the conversation. Hooks run in registration order.
+pub trait PostToolHook: Send + Sync {
+    /// Inspect or transform a completed tool result.
+    fn after(&self, result: ToolResult) -> ToolResult;
 }
 
 /// Configuration settings for the agent
@@ -36,11 +129,36 @@ pub struct AgentConfig {
     /// Whether to automatically compress older context
     pub auto_compress_context: bool,
 
+    /// Maximum number of tool calls to execute concurrently within a single step,
+    /// to cap parallelism against rate-limited backends
+    pub max_concurrent_tools: usize,
+
+    /// Maximum number of generate/tool round-trips to run for a single input
+    /// before giving up, to guard against runaway agentic loops
+    pub max_tool_steps: usize,
+
+    /// Models the agent may target, each selecting a provider backend. The first
+    /// entry is the active model; its `max_tokens` drives the context window.
+    pub models: Vec<crate::agent::backends::ModelConfig>,
+
     /// AWS region to use
     pub aws_region: String,
 
     /// AWS profile to use
     pub aws_profile: Option<String>,
+
+    /// Optional approval gate invoked before any side-effecting ("mutating") tool
+    /// runs. When `None`, mutating tools run without prompting.
+    pub approval_callback: Option<ApprovalCallback>,
+
+    /// Optional sink for streaming response deltas. When set, generation runs
+    /// through the backend's streaming entry point so the UI can render live tokens.
+    pub stream_callback: Option<StreamCallback>,
+
+    /// Whether to dump the per-request state-transition history through tracing when a
+    /// request completes. Defaults to `false`; the `--trace` command-line flag does not
+    /// set it.
+    pub trace_state_history: bool,
 }
 
 impl Default for AgentConfig {
@@ -49,8 +167,16 @@ impl Default for AgentConfig {
             use_fast_model_for_context: true,
             max_context_length: 32000,
             auto_compress_context: true,
+            max_concurrent_tools: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4),
+            max_tool_steps: 10,
+            models: vec![crate::agent::backends::ModelConfig::default()],
             aws_region: "us-east-1".to_string(),
             aws_profile: None,
+            approval_callback: None,
+            stream_callback: None,
+            trace_state_history: false,
         }
     }
 }
@@ -59,31 +185,41 @@ impl AgentManager {
     /// Create a new agent manager with default settings
     pub async fn new() -> Self {
         let config = AgentConfig::default();
-        let backend = GamecodeBridge::new(&config.aws_region, config.aws_profile.clone())
+        let backend = GamecodeBridge::new(&config.models, &config.aws_region, config.aws_profile.clone())
             .await
             .expect("Failed to create GamecodeBridge");
             
+        let (state_tx, _) = watch::channel(AgentState::Idle);
         Self {
             backend,
             tool_registry: ToolRegistry::new(),
             context_manager: ContextManager::new(),
             config,
             initialized: false,
+            state_tx,
+            state_history: vec![AgentState::Idle],
+            pre_tool_hooks: Vec::new(),
+            post_tool_hooks: Vec::new(),
         }
     }
 
     /// Create a new agent manager with custom configuration
     pub async fn with_config(config: AgentConfig) -> Self {
-        let backend = GamecodeBridge::new(&config.aws_region, config.aws_profile.clone())
+        let backend = GamecodeBridge::new(&config.models, &config.aws_region, config.aws_profile.clone())
             .await
             .expect("Failed to create GamecodeBridge");
             
+        let (state_tx, _) = watch::channel(AgentState::Idle);
         Self {
             backend,
             tool_registry: ToolRegistry::new(),
             context_manager: ContextManager::new(),
             config,
             initialized: false,
+            state_tx,
+            state_history: vec![AgentState::Idle],
+            pre_tool_hooks: Vec::new(),
+            post_tool_hooks: Vec::new(),
         }
     }
 
@@ -92,6 +228,16 @@ impl AgentManager {
         self.tool_registry.register_tool(tool);
     }
 
+    /// Register a hook to run before every tool call, appended after existing hooks.
+    pub fn register_pre_tool_hook(&mut self, hook: Arc<dyn PreToolHook>) {
+        self.pre_tool_hooks.push(hook);
+    }
+
+    /// Register a hook to run after every tool call, appended after existing hooks.
+    pub fn register_post_tool_hook(&mut self, hook: Arc<dyn PostToolHook>) {
+        self.post_tool_hooks.push(hook);
+    }
+
     /// Set the working directory for tool execution
     pub fn set_working_directory(&mut self, directory: &str) {
         self.tool_registry.set_working_directory(directory);
@@ -114,6 +260,47 @@ impl AgentManager {
         self.initialized
     }
 
+    /// Subscribe to live [`AgentState`] transitions. The returned receiver holds the
+    /// current state and is notified of later transitions; a watch channel keeps only
+    /// the latest value, so a receiver that falls behind sees the most recent state
+    /// rather than every intermediate one (see [`Self::state_history`] for the full
+    /// sequence).
+    pub fn subscribe_state(&self) -> watch::Receiver<AgentState> {
+        self.state_tx.subscribe()
+    }
+
+    /// The current agent state.
+    pub fn state(&self) -> AgentState {
+        self.state_tx.borrow().clone()
+    }
+
+    /// The ordered history of state transitions for the current request, for debugging.
+    pub fn state_history(&self) -> &[AgentState] {
+        &self.state_history
+    }
+
+    /// Emit the full ordered state-transition history through tracing, so the
+    /// tool-call timeline is auditable. Called at the end of each request when
+    /// [`AgentConfig::trace_state_history`] is set.
+    pub fn log_state_history(&self) {
+        info!(
+            "Agent state history ({} transitions):",
+            self.state_history.len()
+        );
+        for (i, state) in self.state_history.iter().enumerate() {
+            info!("  [{}] {:?}", i, state);
+        }
+    }
+
+    /// Record and publish a state transition.
+    fn set_state(&mut self, state: AgentState) {
+        trace!("Agent state -> {:?}", state);
+        self.state_history.push(state.clone());
+        // `send_replace` stores the value even when no receiver is subscribed, so
+        // `state()` and later subscribers still see it when no UI is watching.
+        self.state_tx.send_replace(state);
+    }
+
     /// Process user input and generate a response
     pub async fn process_input(&mut self, input: &str) -> Result<AgentResponse, String> {
         info!("Processing user input: {} chars", input.len());
@@ -123,100 +310,138 @@ impl AgentManager {
             return Err("Backend not initialized. Call init() first.".to_string());
         }
 
+        // Start a fresh transition history for this request so it reflects a single
+        // turn and doesn't grow unbounded across the life of the session.
+        self.state_history.clear();
+
         // First, update context with user input
         self.context_manager.add_user_message(input);
         info!("Context updated with user message");
 
-        // Prepare context for LLM
-        let context = self.context_manager.get_context();
-        info!("Prepared context for LLM: {} chars", context.len());
-
-        // Process with LLM
-        info!("Sending request to LLM backend...");
-        let backend_response = self
-            .backend
-            .generate_response(&context)
-            .await
-            .map_err(|e| {
-                error!("Backend error: {}", e);
-                format!("Backend error: {}", e)
-            })?;
-        info!(
-            "Received response from LLM: {} chars",
-            backend_response.content.len()
-        );
+        // Drive the agentic loop: generate, execute any requested tools, feed the
+        // results back through the backend, and repeat until the model stops asking
+        // for tools or we hit the configured step cap. Assistant text is accumulated
+        // across steps so the final response reflects the whole chain of thought.
+        let mut steps: Vec<AgentStep> = Vec::new();
+        let mut content = String::new();
+        let mut last_signature: Option<String> = None;
 
-        // Get tool calls directly from the backend response
-        info!("Processing tool calls from response");
-        // Extract tool calls directly from the structured response
-        let tool_calls: Vec<ToolCall> = backend_response
-            .tool_calls
-            .iter()
-            .map(|tc| {
-                let args = tc
-                    .args
-                    .iter()
-                    .map(|(k, v)| format!("{}={}", k, v))
-                    .collect();
+        for step in 0..self.config.max_tool_steps {
+            // Prepare context for LLM (now includes any prior tool results)
+            let context = self.context_manager.get_context();
+            info!(
+                "Step {}: prepared context for LLM: {} chars",
+                step,
+                context.len()
+            );
 
-                // Log the tool call ID to track it through the system
-                if let Some(id) = &tc.id {
-                    trace!("Received tool call with ID '{}' for tool '{}'", id, tc.name);
-                } else {
-                    warn!("Received tool call without ID for tool '{}'", tc.name);
+            // Process with LLM, streaming deltas to the UI when a sink is configured
+            info!("Sending request to LLM backend...");
+            self.set_state(AgentState::Thinking);
+            let generated = if let Some(sink) = &self.config.stream_callback {
+                let sink = sink.clone();
+                self.backend
+                    .generate_response_streaming(&context, Box::new(move |delta| sink(delta)))
+                    .await
+            } else {
+                self.backend.generate_response(&context).await
+            };
+            let backend_response = match generated {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("Backend error: {}", e);
+                    let message = format!("Backend error: {}", e);
+                    self.set_state(AgentState::Error { message: message.clone() });
+                    return Err(message);
                 }
+            };
+            info!(
+                "Received response from LLM: {} chars",
+                backend_response.content.len()
+            );
 
-                ToolCall {
-                    name: tc.name.clone(),
-                    args,
-                    args_json: Some(tc.args.clone()),
-                    id: tc.id.clone(),
+            // Record the assistant turn in context and accumulate its text
+            self.context_manager
+                .add_assistant_message(&backend_response.content);
+            if !backend_response.content.is_empty() {
+                if !content.is_empty() {
+                    content.push('\n');
                 }
-            })
-            .collect();
+                content.push_str(&backend_response.content);
+            }
 
-        info!("Found {} tool calls in backend response", tool_calls.len());
-        info!("Processing {} tool calls", tool_calls.len());
+            // Extract tool calls directly from the structured response
+            let tool_calls = Self::extract_tool_calls(&backend_response);
+            info!("Found {} tool calls in backend response", tool_calls.len());
+
+            // No tools requested: the model is done, so this is the final step
+            if tool_calls.is_empty() {
+                info!("No tool calls to execute, loop complete");
+                steps.push(AgentStep {
+                    content: backend_response.content,
+                    tool_results: Vec::new(),
+                });
+                break;
+            }
 
-        // Execute any tool calls
-        let tool_results = if !tool_calls.is_empty() {
-            info!("Executing tool calls");
-            self.execute_tool_calls(tool_calls).await?
-        } else {
-            info!("No tool calls to execute");
-            Vec::new()
-        };
+            // Guard against infinite loops: if the model asks for exactly the same
+            // tool+args pair it just asked for, it is not making progress and we bail.
+            let signature = Self::tool_calls_signature(&tool_calls);
+            if last_signature.as_deref() == Some(signature.as_str()) {
+                warn!("Tool call signature repeated with no progress, breaking loop");
+                steps.push(AgentStep {
+                    content: backend_response.content,
+                    tool_results: Vec::new(),
+                });
+                break;
+            }
+            last_signature = Some(signature);
 
-        // Add assistant response to context
-        self.context_manager
-            .add_assistant_message(&backend_response.content);
-        info!("Added assistant response to context");
+            // Execute the requested tools
+            info!("Executing {} tool calls", tool_calls.len());
+            let state_name = if tool_calls.len() == 1 {
+                tool_calls[0].name.clone()
+            } else {
+                format!("{} tools", tool_calls.len())
+            };
+            self.set_state(AgentState::ExecutingTool { name: state_name });
+            let tool_results = self.execute_tool_calls(tool_calls).await?;
 
-        // Add tool results to context if any
-        if !tool_results.is_empty() {
+            // Add tool results to context so the next step can see them
             info!("Adding {} tool results to context", tool_results.len());
-            
-            // Log each tool result being added
             for (i, result) in tool_results.iter().enumerate() {
-                trace!("Tool result {}: name={}, id={:?}, content length={}", 
-                      i, 
-                      result.tool_name, 
-                      result.tool_call_id, 
+                trace!("Tool result {}: name={}, id={:?}, content length={}",
+                      i,
+                      result.tool_name,
+                      result.tool_call_id,
                       result.result.len());
-                
+
                 // Log the beginning of the content to help debug formatting issues
                 if result.tool_name == "read_file" {
-                    trace!("read_file result first 200 chars: {}", 
-                          if result.result.len() > 200 { 
-                              &result.result[..200] 
-                          } else { 
-                              &result.result 
+                    trace!("read_file result first 200 chars: {}",
+                          if result.result.len() > 200 {
+                              &result.result[..200]
+                          } else {
+                              &result.result
                           });
                     trace!("IMPORTANT: read_file result must be passed as raw text without JSON serialization");
                 }
             }
-            
             self.context_manager.add_tool_results(&tool_results);
+
+            steps.push(AgentStep {
+                content: backend_response.content,
+                tool_results,
+            });
+        }
+
+        if steps.len() == self.config.max_tool_steps
+            && steps.last().is_some_and(|s| !s.tool_results.is_empty())
+        {
+            warn!(
+                "Reached max_tool_steps ({}) without a final tool-free response",
+                self.config.max_tool_steps
+            );
         }
 
         // Compress context if needed
@@ -224,76 +449,224 @@ impl AgentManager {
             self.maybe_compress_context().await?;
         }
 
-        info!("Processing complete, returning response");
+        // Flatten every step's tool results for backward-compatible consumers
+        let tool_results: Vec<ToolResult> = steps
+            .iter()
+            .flat_map(|s| s.tool_results.iter().cloned())
+            .collect();
+
+        self.set_state(AgentState::Idle);
+        // In --trace mode, dump the full transition history for this request.
+        if self.config.trace_state_history {
+            self.log_state_history();
+        }
+        info!("Processing complete, returning response over {} steps", steps.len());
         Ok(AgentResponse {
-            content: backend_response.content,
+            content,
             tool_results,
+            steps,
         })
     }
 
-    // Removed parse_tool_calls - Using structured tool calls directly
+    /// Extract structured tool calls from a backend response.
+    fn extract_tool_calls(backend_response: &crate::agent::backends::BackendResponse) -> Vec<ToolCall> {
+        backend_response
+            .tool_calls
+            .iter()
+            .map(|tc| {
+                let args = tc
+                    .args
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect();
+
+                // Log the tool call ID to track it through the system
+                if let Some(id) = &tc.id {
+                    trace!("Received tool call with ID '{}' for tool '{}'", id, tc.name);
+                } else {
+                    warn!("Received tool call without ID for tool '{}'", tc.name);
+                }
+
+                ToolCall {
+                    name: tc.name.clone(),
+                    args,
+                    args_json: Some(tc.args.clone()),
+                    id: tc.id.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Build a stable signature for a batch of tool calls so we can detect a model
+    /// repeating the same request without making progress.
+    fn tool_calls_signature(tool_calls: &[ToolCall]) -> String {
+        tool_calls
+            .iter()
+            .map(|tc| {
+                let mut args = tc.args.clone();
+                args.sort();
+                format!("{}({})", tc.name, args.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
 
-    /// Execute any tool calls found in the response
+    /// Execute any tool calls found in the response.
+    ///
+    /// Independent tool calls run concurrently, bounded by `max_concurrent_tools`,
+    /// so the model can request several reads in one turn without paying for them
+    /// serially. Result ordering matches the request order, each call's exact
+    /// `tool_call_id` is preserved, and a failing call is turned into a `ToolResult`
+    /// describing the failure rather than aborting the whole batch.
     async fn execute_tool_calls(
-        &self,
+        &mut self,
         tool_calls: Vec<ToolCall>,
     ) -> Result<Vec<ToolResult>, String> {
-        let mut results = Vec::new();
-
-        for tool_call in tool_calls {
-            // Convert args to JSON Value for the bridge
-            let args_json = if let Some(ref args_map) = tool_call.args_json {
-                serde_json::to_value(args_map).unwrap_or(Value::Object(serde_json::Map::new()))
-            } else {
-                // Fallback: convert string args to JSON
-                let mut args_map = serde_json::Map::new();
-                for arg in &tool_call.args {
-                    if let Some((key, value)) = arg.split_once('=') {
-                        args_map.insert(key.to_string(), Value::String(value.to_string()));
+        use futures::stream::{self, StreamExt};
+
+        // Phase 1: resolve interactive approvals sequentially. Doing this here, rather
+        // than inside the concurrent executor, guarantees the UI is asked to approve at
+        // most one tool at a time (the approval callback is not assumed re-entrant), and
+        // lets each AwaitingApproval transition be recorded in the state history for
+        // --trace. `slots` keeps request order; approved calls are deferred to phase 2.
+        let mut slots: Vec<Option<ToolResult>> = Vec::with_capacity(tool_calls.len());
+        let mut to_execute: Vec<(usize, ToolCall)> = Vec::new();
+        for (idx, tool_call) in tool_calls.into_iter().enumerate() {
+            slots.push(None);
+            if self.backend.tool_may_mutate(&tool_call.name) {
+                if let Some(callback) = self.config.approval_callback.clone() {
+                    info!("Requesting approval for mutating tool '{}'", tool_call.name);
+                    self.set_state(AgentState::AwaitingApproval {
+                        name: tool_call.name.clone(),
+                    });
+                    let args_json = Self::tool_call_args_json(&tool_call);
+                    let approved = callback(tool_call.name.clone(), args_json).await;
+                    if !approved {
+                        warn!("Approval denied for tool '{}', skipping execution", tool_call.name);
+                        slots[idx] = Some(self.apply_post_hooks(ToolResult {
+                            tool_name: tool_call.name.clone(),
+                            result: format!(
+                                "Tool '{}' was not executed because the user declined approval.",
+                                tool_call.name
+                            ),
+                            tool_call_id: tool_call.id.clone(),
+                        }));
+                        continue;
                     }
                 }
-                Value::Object(args_map)
-            };
-            
-            let result = self.backend
-                .execute_tool(&tool_call.name, &args_json)
-                .await
-                .map_err(|e| format!("Tool execution error: {}", e))?;
-
-            // CRITICAL: Make sure we're preserving the original ID from Claude's tool_use block
-            // This ID must match EXACTLY for Claude's API validation - even a single character difference will fail
-            let tool_call_id = tool_call.id.clone();
-            if let Some(id) = &tool_call_id {
-                trace!(
-                    "USING EXACT Claude-provided tool_use_id: '{}' for result of tool '{}'",
-                    id,
-                    tool_call.name
-                );
-                trace!(
-                    "ID MUST NOT be modified in any way - even a single character difference will cause validation to fail"
-                );
-            } else {
-                // This should never happen with Claude tool calls, and will cause validation to fail
-                warn!(
-                    "CRITICAL ERROR: Missing tool ID for tool '{}', Claude will reject the result",
-                    tool_call.name
-                );
             }
+            to_execute.push((idx, tool_call));
+        }
 
-            // Pass the exact same ID to the result
-            results.push(ToolResult {
-                tool_name: tool_call.name.clone(),
-                result,
-                tool_call_id: tool_call_id, // This must be passed unmodified to context.rs
-            });
+        // Phase 2: execute the approved calls concurrently, bounded by
+        // `max_concurrent_tools`. Results are slotted back by index so the final order
+        // matches the request order even though execution completes out of order.
+        let concurrency = self.config.max_concurrent_tools.max(1);
+        let this: &Self = self; // shared reborrow so it can be copied into each future
+        let executed = stream::iter(to_execute)
+            .map(|(idx, tool_call)| async move {
+                (idx, this.execute_single_tool_call(tool_call).await)
+            })
+            .buffer_unordered(concurrency)
+            .collect::<Vec<(usize, ToolResult)>>()
+            .await;
+        for (idx, result) in executed {
+            slots[idx] = Some(result);
+        }
+
+        Ok(slots
+            .into_iter()
+            .map(|slot| slot.expect("every tool slot resolved"))
+            .collect())
+    }
+
+    /// Convert a tool call's arguments into a JSON value for the bridge.
+    fn tool_call_args_json(tool_call: &ToolCall) -> Value {
+        if let Some(ref args_map) = tool_call.args_json {
+            serde_json::to_value(args_map).unwrap_or(Value::Object(serde_json::Map::new()))
+        } else {
+            // Fallback: convert string args to JSON
+            let mut args_map = serde_json::Map::new();
+            for arg in &tool_call.args {
+                if let Some((key, value)) = arg.split_once('=') {
+                    args_map.insert(key.to_string(), Value::String(value.to_string()));
+                }
+            }
+            Value::Object(args_map)
+        }
+    }
+
+    /// Execute a single, already-approved tool call, running pre/post hooks around the
+    /// dispatch and mapping errors into a `ToolResult`. Always resolves to a result
+    /// carrying the original `tool_call_id` so one failure never stalls the batch.
+    async fn execute_single_tool_call(&self, tool_call: ToolCall) -> ToolResult {
+        // Convert args to JSON Value for the bridge
+        let mut args_json = Self::tool_call_args_json(&tool_call);
+
+        // CRITICAL: Make sure we're preserving the original ID from Claude's tool_use block
+        // This ID must match EXACTLY for Claude's API validation - even a single character difference will fail
+        let tool_call_id = tool_call.id.clone();
+        if let Some(id) = &tool_call_id {
+            trace!(
+                "USING EXACT Claude-provided tool_use_id: '{}' for result of tool '{}'",
+                id,
+                tool_call.name
+            );
+            trace!(
+                "ID MUST NOT be modified in any way - even a single character difference will cause validation to fail"
+            );
+        } else {
+            // This should never happen with Claude tool calls, and will cause validation to fail
+            warn!(
+                "CRITICAL ERROR: Missing tool ID for tool '{}', Claude will reject the result",
+                tool_call.name
+            );
+        }
+
+        // Run pre-hooks in order. Each may rewrite the args, inject context, or veto
+        // the call by returning a replacement result that short-circuits execution.
+        for hook in &self.pre_tool_hooks {
+            match hook.before(&tool_call.name, args_json) {
+                PreHookOutcome::Continue(rewritten) => args_json = rewritten,
+                PreHookOutcome::Replace(mut replacement) => {
+                    trace!("Pre-hook short-circuited tool '{}'", tool_call.name);
+                    // Preserve the exact tool_call_id regardless of what the hook set.
+                    replacement.tool_call_id = tool_call_id;
+                    return self.apply_post_hooks(replacement);
+                }
+            }
         }
 
-        Ok(results)
+        // Surface per-call errors as a ToolResult rather than aborting the batch,
+        // so the model still receives a result for every tool_call_id it issued.
+        let result = match self.backend.execute_tool(&tool_call.name, &args_json).await {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Tool '{}' failed: {}", tool_call.name, e);
+                format!("Tool '{}' failed: {}", tool_call.name, e)
+            }
+        };
+
+        // Pass the exact same ID to the result, then let post-hooks transform it
+        self.apply_post_hooks(ToolResult {
+            tool_name: tool_call.name.clone(),
+            result,
+            tool_call_id, // This must be passed unmodified to context.rs
+        })
+    }
+
+    /// Run the registered post-hooks over a tool result, in order.
+    fn apply_post_hooks(&self, mut result: ToolResult) -> ToolResult {
+        for hook in &self.post_tool_hooks {
+            result = hook.after(result);
+        }
+        result
     }
 
     /// Compress context if it gets too large
     async fn maybe_compress_context(&mut self) -> Result<(), String> {
         if self.context_manager.context_length() > self.config.max_context_length {
+            self.set_state(AgentState::CompressingContext);
             // Note: In the modular architecture, model selection is handled by the backend
             // We'll use the same backend for compression - it will use appropriate models internally
 
@@ -339,6 +712,7 @@ pub struct ToolCall {
 }
 
 /// Structure representing the result of a tool execution
+#[derive(Clone)]
 pub struct ToolResult {
     /// Name of the tool that was executed
     pub tool_name: String,
@@ -351,8 +725,24 @@ pub struct ToolResult {
     pub tool_call_id: Option<String>,
 }
 
+/// A single step in the agentic loop: the assistant text produced at that step
+/// plus any tool results gathered before handing control back to the model.
+pub struct AgentStep {
+    /// Assistant text emitted during this step
+    pub content: String,
+
+    /// Tool results produced during this step (empty on the final, tool-free step)
+    pub tool_results: Vec<ToolResult>,
+}
+
 /// Structure representing a complete response from the agent
 pub struct AgentResponse {
+    /// Assistant text accumulated across every step of the loop
     pub content: String,
+
+    /// All tool results produced across the loop, flattened for convenience
     pub tool_results: Vec<ToolResult>,
+
+    /// Per-step breakdown so the UI can render the chain of thought
+    pub steps: Vec<AgentStep>,
 }